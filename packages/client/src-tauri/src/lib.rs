@@ -1,10 +1,162 @@
-use tauri::Emitter;
+use tauri::{Emitter, Listener, Manager};
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIcon;
 use tauri_plugin_shell::{ShellExt, process::CommandEvent};
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
+use std::collections::VecDeque;
+use std::net::TcpListener;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use log::{error, info};
+
+// Maximum number of log lines kept in memory so a window opened after
+// startup can still backfill history.
+const MAX_LOG_LINES: usize = 1000;
+
+// Preferred port and the size of the range we'll scan if it's taken.
+const DEFAULT_PORT: u16 = 3147;
+const PORT_SCAN_RANGE: u16 = 50;
+
+// Backoff schedule for auto-restarting a crashed sidecar: 1s, 2s, 4s, ...
+// capped at 30s, giving up after this many consecutive failures.
+const INITIAL_RESTART_DELAY_MS: u64 = 1_000;
+const MAX_RESTART_DELAY_MS: u64 = 30_000;
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+// A sidecar that stays up this long is considered healthy again, so the
+// next crash starts the backoff counter from scratch.
+const HEALTHY_UPTIME: Duration = Duration::from_secs(60);
+
+// Broadcast alongside `promptliano-server-restarting` so the UI can show
+// which attempt is in flight and how long it's waiting before retrying.
+#[derive(Clone, Serialize)]
+struct RestartInfo {
+    attempt: u32,
+    delay_ms: u64,
+}
+
+// Returns `preferred` if nothing is listening on it, otherwise scans
+// upward through `PORT_SCAN_RANGE` candidates for the first free one.
+fn find_available_port(preferred: u16) -> Option<u16> {
+    (preferred..preferred.saturating_add(PORT_SCAN_RANGE))
+        .find(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
+
+// How often the readiness watcher polls `/api/health`, and how long it
+// waits in total before giving up on the sidecar ever coming up.
+const READINESS_POLL_INTERVAL_MS: u64 = 500;
+const READINESS_TIMEOUT_MS: u64 = 30_000;
+
+// Hits the sidecar's health endpoint on `port` and reports whether it
+// responded successfully. Shared by the readiness watcher and the
+// `check_server_status` command.
+async fn check_health(port: u16) -> bool {
+    match reqwest::get(format!("http://localhost:{}/api/health", port)).await {
+        Ok(response) => response.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+// A single log line surfaced to the frontend console, whether it came from
+// the sidecar's stdout/stderr or from Tauri's own diagnostics.
+#[derive(Clone, Serialize)]
+struct LogEvent {
+    level: String,
+    timestamp: u64,
+    stream: String,
+    message: String,
+}
+
+impl LogEvent {
+    fn new(level: impl Into<String>, stream: impl Into<String>, message: impl Into<String>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            level: level.into(),
+            timestamp,
+            stream: stream.into(),
+            message: message.into(),
+        }
+    }
+}
+
+// Stores the captured Tauri app handle so the fern logger (installed before
+// any window exists) can still reach `ServerState` and emit events.
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+// The tray icon and the menu items whose enabled state tracks whether the
+// sidecar is running. Populated once in `setup_tray`.
+static TRAY_ICON: OnceLock<TrayIcon> = OnceLock::new();
+static TRAY_ITEMS: OnceLock<TrayMenuItems> = OnceLock::new();
+
+struct TrayMenuItems {
+    start_item: MenuItem<tauri::Wry>,
+    stop_item: MenuItem<tauri::Wry>,
+}
 
 // Store the child process handle globally so we can kill it on app exit
 struct ServerState {
     child: Option<tauri_plugin_shell::process::CommandChild>,
+    logs: VecDeque<LogEvent>,
+    port: Option<u16>,
+    // Set while a start is in flight (port picked, sidecar not yet stored)
+    // so a second concurrent start is rejected instead of spawning an
+    // orphaned extra process. See `try_reserve_start`.
+    starting: bool,
+    // Set while `stop_promptliano_server` is tearing the sidecar down so
+    // the supervisor doesn't treat that termination as a crash.
+    manual_stop: bool,
+    // Consecutive restart attempts since the sidecar last stayed healthy
+    // for `HEALTHY_UPTIME`.
+    restart_attempt: u32,
+    started_at: Option<Instant>,
+    // Bumped every time a new child is stored. The background task and
+    // `handle_termination` for a given spawn capture this value so a
+    // `CommandEvent::Terminated` for a since-superseded child (e.g. one
+    // force-killed by `stop_sidecar` after a fresh one was already
+    // started) is recognized as stale and ignored instead of clobbering
+    // the state of the currently running instance.
+    generation: u64,
+}
+
+// Appends a log line to the bounded ring buffer and emits it to the
+// frontend so an auto-scrolling console can render it live.
+fn record_log(app: &tauri::AppHandle, state: &Mutex<ServerState>, event: LogEvent) {
+    {
+        let mut state_guard = state.lock().unwrap();
+        if state_guard.logs.len() >= MAX_LOG_LINES {
+            state_guard.logs.pop_front();
+        }
+        state_guard.logs.push_back(event.clone());
+    }
+    let _ = app.emit("promptliano-server-log", event);
+}
+
+// Installs a fern logger so Tauri-side diagnostics flow through the same
+// `promptliano-server-log` channel as the sidecar's stdout/stderr. Scoped
+// to this crate's own target so dependencies that also log through the
+// `log` facade (reqwest, tauri/wry internals, ...) don't flood the bounded
+// log-console ring buffer.
+fn init_logger() {
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!("[Tauri] [{}] {}", record.level(), message))
+        })
+        .level(log::LevelFilter::Warn)
+        .level_for(module_path!(), log::LevelFilter::Info)
+        .chain(std::io::stdout())
+        .chain(fern::Output::call(|record| {
+            if !record.target().starts_with(module_path!()) {
+                return;
+            }
+            let Some(app) = APP_HANDLE.get() else { return };
+            let Some(state) = app.try_state::<Mutex<ServerState>>() else { return };
+            let event = LogEvent::new(record.level().to_string(), "tauri", record.args().to_string());
+            record_log(app, &state, event);
+        }))
+        .apply()
+        .ok();
 }
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -13,77 +165,95 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[tauri::command]
-async fn start_promptliano_server(
-    app: tauri::AppHandle,
-    state: tauri::State<'_, Mutex<ServerState>>
-) -> Result<String, String> {
-    println!("[Tauri] start_promptliano_server called");
+// Atomically checks whether a start can proceed and, if so, reserves it by
+// setting `starting`. Holding the lock across the check-and-set (rather
+// than each caller separately checking `child.is_some()` before an
+// `await`) is what prevents two near-simultaneous start requests - the
+// window button and the tray, or a double click - from both passing the
+// check and spawning two sidecars. Callers must pair a successful
+// reservation with `clear_starting` once the spawn attempt finishes.
+fn try_reserve_start(state: &Mutex<ServerState>) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    if state_guard.child.is_some() || state_guard.starting {
+        return Err("Promptliano server is already running".to_string());
+    }
+    state_guard.starting = true;
+    Ok(())
+}
+
+fn clear_starting(state: &Mutex<ServerState>) {
+    state.lock().unwrap().starting = false;
+}
+
+// Spawns the sidecar, stores the child/port in `ServerState`, and starts
+// the background task that forwards its output and supervises it. Used
+// both by the `start_promptliano_server` command and by the restart
+// supervisor, so a crash and a manual start go through the same path.
+async fn spawn_sidecar(app: tauri::AppHandle) -> Result<u16, String> {
     let shell = app.shell();
-    
-    // Check if server is already running
-    {
-        let state_guard = state.lock().unwrap();
-        if state_guard.child.is_some() {
-            println!("[Tauri] Server already running");
-            return Ok("Promptliano server is already running".to_string());
-        }
+    let state = app.state::<Mutex<ServerState>>();
+
+    let port = find_available_port(DEFAULT_PORT).ok_or_else(|| {
+        let msg = format!(
+            "No free port found in range {}-{}",
+            DEFAULT_PORT,
+            DEFAULT_PORT + PORT_SCAN_RANGE - 1
+        );
+        error!("{}", msg);
+        msg
+    })?;
+    if port != DEFAULT_PORT {
+        info!("Port {} is taken, using {} instead", DEFAULT_PORT, port);
     }
-    
-    println!("[Tauri] Spawning server sidecar...");
+
+    info!("Spawning server sidecar on port {}...", port);
     let (mut rx, child) = shell
         .sidecar("promptliano-server")
         .map_err(|e| {
-            eprintln!("[Tauri] Failed to spawn sidecar: {}", e);
+            error!("Failed to spawn sidecar: {}", e);
             e.to_string()
         })?
-        .args(["--port", "3147"])
+        .args(["--port", &port.to_string()])
         .spawn()
         .map_err(|e| {
-            eprintln!("[Tauri] Failed to spawn process: {}", e);
+            error!("Failed to spawn process: {}", e);
             e.to_string()
         })?;
-    
-    println!("[Tauri] Sidecar spawned successfully");
 
-    // Store the child process
-    {
+    info!("Sidecar spawned successfully");
+
+    // Store the child process and the port it was started on
+    let generation = {
         let mut state_guard = state.lock().unwrap();
         state_guard.child = Some(child);
-    }
+        state_guard.port = Some(port);
+        state_guard.manual_stop = false;
+        state_guard.started_at = Some(Instant::now());
+        state_guard.generation += 1;
+        state_guard.generation
+    };
 
     let app_handle = app.clone();
-    
-    // Handle process output in background
+
+    // Handle process output in background. This is purely for the log
+    // console now - liveness is decided by `spawn_readiness_watcher` below.
     tauri::async_runtime::spawn(async move {
-        let mut server_ready = false;
-        
+        let log_state = app_handle.state::<Mutex<ServerState>>();
+
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line) => {
-                    let line_str = String::from_utf8_lossy(&line);
-                    println!("[Tauri] Server stdout: {}", line_str);
-                    
-                    // Check if server is ready - look for multiple patterns
-                    if !server_ready && (
-                        line_str.contains("Server running") || 
-                        line_str.contains("Listening on") ||
-                        line_str.contains("[Server] Server running at")
-                    ) {
-                        println!("[Tauri] Server ready detected!");
-                        server_ready = true;
-                        // Emit event to frontend
-                        app_handle.emit("promptliano-server-ready", ()).unwrap();
-                    }
+                    let line_str = String::from_utf8_lossy(&line).to_string();
+                    record_log(&app_handle, &log_state, LogEvent::new("info", "stdout", line_str));
                 }
                 CommandEvent::Stderr(line) => {
-                    let line_str = String::from_utf8_lossy(&line);
-                    eprintln!("[Tauri] Server stderr: {}", line_str);
+                    let line_str = String::from_utf8_lossy(&line).to_string();
+                    record_log(&app_handle, &log_state, LogEvent::new("error", "stderr", line_str));
                 }
                 CommandEvent::Terminated(payload) => {
-                    println!("[Tauri] Server terminated with code: {:?}", payload.code);
-                    // Emit event to frontend
+                    info!("Server terminated with code: {:?}", payload.code);
                     app_handle.emit("promptliano-server-terminated", payload.code).unwrap();
+                    handle_termination(app_handle.clone(), generation);
                     break;
                 }
                 _ => {}
@@ -91,43 +261,322 @@ async fn start_promptliano_server(
         }
     });
 
-    Ok("Promptliano server starting on port 3147".to_string())
+    spawn_readiness_watcher(app, port);
+
+    Ok(port)
+}
+
+// Polls `/api/health` at a short interval until it succeeds or
+// `READINESS_TIMEOUT_MS` elapses, decoupling liveness detection from the
+// sidecar's stdout wording. Emits `promptliano-server-ready` on success or
+// `promptliano-server-start-timeout` if readiness is never reached.
+fn spawn_readiness_watcher(app: tauri::AppHandle, port: u16) {
+    tauri::async_runtime::spawn(async move {
+        let deadline = Instant::now() + Duration::from_millis(READINESS_TIMEOUT_MS);
+
+        while Instant::now() < deadline {
+            if check_health(port).await {
+                info!("Server ready on port {}", port);
+                let _ = app.emit("promptliano-server-ready", ());
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(READINESS_POLL_INTERVAL_MS)).await;
+        }
+
+        error!("Server did not become ready on port {} within {}ms", port, READINESS_TIMEOUT_MS);
+        let _ = app.emit("promptliano-server-start-timeout", ());
+    });
+}
+
+// Decides whether the just-terminated sidecar should be restarted, and if
+// so, schedules it with exponential backoff. `generation` is the value
+// captured when the terminated child was spawned; if a newer child has
+// since replaced it in `ServerState`, this termination is stale and is
+// ignored entirely so it can't clobber the currently running instance.
+fn handle_termination(app: tauri::AppHandle, generation: u64) {
+    let state = app.state::<Mutex<ServerState>>();
+
+    // Checked and logged outside the lock: the fern sink installed by
+    // `init_logger` calls back into `record_log`, which locks this same
+    // mutex, so logging while `state_guard` is held would self-deadlock.
+    let stale = state.lock().unwrap().generation != generation;
+    if stale {
+        info!("Ignoring termination of superseded sidecar (generation {})", generation);
+        return;
+    }
+
+    let (should_restart, attempt, delay_ms) = {
+        let mut state_guard = state.lock().unwrap();
+        state_guard.child = None;
+        state_guard.port = None;
+
+        if state_guard.manual_stop {
+            state_guard.manual_stop = false;
+            return;
+        }
+
+        // A sidecar that ran long enough to be considered healthy resets
+        // the backoff counter, so a later crash starts from attempt 1 again.
+        if state_guard.started_at.map_or(false, |t| t.elapsed() >= HEALTHY_UPTIME) {
+            state_guard.restart_attempt = 0;
+        }
+        state_guard.restart_attempt += 1;
+
+        if state_guard.restart_attempt > MAX_RESTART_ATTEMPTS {
+            (false, state_guard.restart_attempt, 0)
+        } else {
+            let delay_ms = INITIAL_RESTART_DELAY_MS
+                .saturating_mul(1 << (state_guard.restart_attempt - 1))
+                .min(MAX_RESTART_DELAY_MS);
+            (true, state_guard.restart_attempt, delay_ms)
+        }
+    };
+
+    if !should_restart {
+        error!("Server crashed {} times, giving up", attempt);
+        let _ = app.emit("promptliano-server-gave-up", ());
+        return;
+    }
+
+    info!("Restarting server: attempt {} in {}ms", attempt, delay_ms);
+    let _ = app.emit("promptliano-server-restarting", RestartInfo { attempt, delay_ms });
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        let state = app.state::<Mutex<ServerState>>();
+        if let Err(e) = try_reserve_start(&state) {
+            // A manual start already beat the supervisor to it; leave it alone.
+            info!("Skipping restart, a start is already in progress: {}", e);
+            return;
+        }
+        let result = spawn_sidecar(app.clone()).await;
+        clear_starting(&state);
+        if let Err(e) = result {
+            error!("Restart attempt {} failed: {}", attempt, e);
+            // Nothing came up, so the backoff wait must not count as uptime.
+            state.lock().unwrap().started_at = None;
+            handle_termination(app, generation);
+        }
+    });
 }
 
 #[tauri::command]
-async fn stop_promptliano_server(
+async fn start_promptliano_server(
+    app: tauri::AppHandle,
     state: tauri::State<'_, Mutex<ServerState>>
 ) -> Result<String, String> {
-    let mut state_guard = state.lock().unwrap();
-    
-    if let Some(child) = state_guard.child.take() {
+    info!("start_promptliano_server called");
+
+    if let Err(msg) = try_reserve_start(&state) {
+        info!("{}", msg);
+        return Ok(msg);
+    }
+
+    let result = spawn_sidecar(app).await;
+    clear_starting(&state);
+    let port = result?;
+    Ok(format!("Promptliano server starting on port {}", port))
+}
+
+// Default budget for an orderly shutdown before we fall back to `kill()`.
+const DEFAULT_SHUTDOWN_TIMEOUT_MS: u64 = 5_000;
+// How often we poll for the sidecar having exited on its own.
+const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
+
+// Whether `stop_sidecar` got the sidecar to exit on its own within the
+// timeout, or had to fall back to killing it.
+#[derive(Clone, Serialize)]
+struct ShutdownResult {
+    graceful: bool,
+}
+
+// Shared by the `stop_promptliano_server` command and the tray's "Stop
+// Server" item. Asks the sidecar to shut down itself, gives it up to
+// `timeout_ms` to exit (watched via the `CommandEvent::Terminated` handler
+// clearing `ServerState::child`), and only kills it if it's still alive
+// after that.
+async fn stop_sidecar(app: tauri::AppHandle, timeout_ms: u64) -> Result<ShutdownResult, String> {
+    let state = app.state::<Mutex<ServerState>>();
+
+    let port = {
+        let mut state_guard = state.lock().unwrap();
+        if state_guard.child.is_none() {
+            return Ok(ShutdownResult { graceful: true });
+        }
+        // Tell the supervisor this termination was requested, not a crash.
+        state_guard.manual_stop = true;
+        state_guard.port
+    };
+
+    if let Some(port) = port {
+        info!("Requesting graceful shutdown on port {}", port);
+        let client = reqwest::Client::new();
+        if let Err(e) = client
+            .post(format!("http://localhost:{}/api/shutdown", port))
+            .timeout(Duration::from_secs(1))
+            .send()
+            .await
+        {
+            info!("Graceful shutdown request failed ({}), waiting for exit anyway", e);
+        }
+    }
+
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    while Instant::now() < deadline {
+        if state.lock().unwrap().child.is_none() {
+            info!("Sidecar shut down gracefully");
+            return Ok(ShutdownResult { graceful: true });
+        }
+        tokio::time::sleep(Duration::from_millis(SHUTDOWN_POLL_INTERVAL_MS)).await;
+    }
+
+    info!("Graceful shutdown timed out, forcing kill");
+    let child = state.lock().unwrap().child.take();
+    if let Some(child) = child {
         child.kill().map_err(|e| e.to_string())?;
+    }
+    state.lock().unwrap().port = None;
+    Ok(ShutdownResult { graceful: false })
+}
+
+#[tauri::command]
+async fn stop_promptliano_server(
+    app: tauri::AppHandle,
+    timeout_ms: Option<u64>,
+) -> Result<String, String> {
+    let result = stop_sidecar(app, timeout_ms.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_MS)).await?;
+    if result.graceful {
         Ok("Promptliano server stopped".to_string())
     } else {
-        Ok("Promptliano server was not running".to_string())
+        Ok("Promptliano server force-stopped after shutdown timeout".to_string())
     }
 }
 
 #[tauri::command]
-async fn check_server_status() -> Result<bool, String> {
-    println!("[Tauri] Checking server status...");
-    // Simple health check by trying to connect to the server
-    match reqwest::get("http://localhost:3147/api/health").await {
-        Ok(response) => {
-            let status = response.status().is_success();
-            println!("[Tauri] Server health check: {}", if status { "OK" } else { "Failed" });
-            Ok(status)
-        },
-        Err(e) => {
-            println!("[Tauri] Server health check error: {}", e);
-            Ok(false)
-        },
+async fn check_server_status(state: tauri::State<'_, Mutex<ServerState>>) -> Result<bool, String> {
+    let port = state.lock().unwrap().port.unwrap_or(DEFAULT_PORT);
+    info!("Checking server status on port {}...", port);
+    let status = check_health(port).await;
+    info!("Server health check: {}", if status { "OK" } else { "Failed" });
+    Ok(status)
+}
+
+// Returns the port the sidecar is currently running on, if it's been
+// started, so the frontend can build request URLs without guessing.
+#[tauri::command]
+fn get_server_port(state: tauri::State<'_, Mutex<ServerState>>) -> Option<u16> {
+    state.lock().unwrap().port
+}
+
+// Returns the buffered log history so a console window opened after
+// startup can backfill everything captured so far.
+#[tauri::command]
+fn get_server_logs(state: tauri::State<'_, Mutex<ServerState>>) -> Vec<LogEvent> {
+    state.lock().unwrap().logs.iter().cloned().collect()
+}
+
+// Reflects whether the sidecar is running in the tray's menu item states
+// and tooltip. Called after every start/stop/crash transition.
+fn sync_tray_menu(running: bool) {
+    if let Some(items) = TRAY_ITEMS.get() {
+        let _ = items.start_item.set_enabled(!running);
+        let _ = items.stop_item.set_enabled(running);
+    }
+    if let Some(tray) = TRAY_ICON.get() {
+        let tooltip = if running {
+            "Promptliano: server running"
+        } else {
+            "Promptliano: server stopped"
+        };
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+// Builds the tray icon with Start/Stop/Open Window/Quit items and wires
+// each to the same logic the equivalent Tauri commands use, plus listens
+// for server lifecycle events so the menu stays in sync.
+fn setup_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let start_item = MenuItem::with_id(app, "start_server", "Start Server", true, None::<&str>)?;
+    let stop_item = MenuItem::with_id(app, "stop_server", "Stop Server", false, None::<&str>)?;
+    let open_item = MenuItem::with_id(app, "open_window", "Open Window", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&start_item, &stop_item, &open_item, &quit_item])?;
+
+    let tray = tauri::tray::TrayIconBuilder::new()
+        .menu(&menu)
+        .tooltip("Promptliano: server stopped")
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "start_server" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let state = app.state::<Mutex<ServerState>>();
+                    if let Err(e) = try_reserve_start(&state) {
+                        info!("{}", e);
+                        return;
+                    }
+                    let result = spawn_sidecar(app).await;
+                    clear_starting(&state);
+                    if let Err(e) = result {
+                        error!("Failed to start server from tray: {}", e);
+                    }
+                });
+            }
+            "stop_server" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = stop_sidecar(app, DEFAULT_SHUTDOWN_TIMEOUT_MS).await {
+                        error!("Failed to stop server from tray: {}", e);
+                    }
+                    sync_tray_menu(false);
+                });
+            }
+            "open_window" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "quit" => {
+                kill_on_exit(app);
+                app.exit(0);
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    TRAY_ICON.set(tray).ok();
+    TRAY_ITEMS.set(TrayMenuItems { start_item, stop_item }).ok();
+
+    // Keep the menu/tooltip in sync with sidecar lifecycle events raised
+    // outside the tray itself (manual start from the main window, crashes).
+    app.listen("promptliano-server-ready", |_| sync_tray_menu(true));
+    app.listen("promptliano-server-terminated", |_| sync_tray_menu(false));
+    app.listen("promptliano-server-gave-up", |_| sync_tray_menu(false));
+
+    Ok(())
+}
+
+// Kills the sidecar without triggering the auto-restart supervisor, used
+// both by the tray's Quit item and the app's own exit handler.
+fn kill_on_exit(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<ServerState>>();
+    // Take the child and drop the guard before logging: the fern sink
+    // installed by `init_logger` locks this same mutex via `record_log`,
+    // so logging while still holding it here would self-deadlock.
+    let child = {
+        let mut state_guard = state.lock().unwrap();
+        state_guard.manual_stop = true;
+        state_guard.child.take()
+    };
+    if let Some(child) = child {
+        info!("Killing sidecar on app exit");
+        let _ = child.kill();
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_http::init())
@@ -135,9 +584,34 @@ pub fn run() {
             greet,
             start_promptliano_server,
             stop_promptliano_server,
-            check_server_status
+            check_server_status,
+            get_server_logs,
+            get_server_port
         ])
-        .manage(Mutex::new(ServerState { child: None }))
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .manage(Mutex::new(ServerState {
+            child: None,
+            logs: VecDeque::with_capacity(MAX_LOG_LINES),
+            port: None,
+            starting: false,
+            manual_stop: false,
+            restart_attempt: 0,
+            started_at: None,
+            generation: 0,
+        }))
+        .setup(|app| {
+            let _ = APP_HANDLE.set(app.handle().clone());
+            init_logger();
+            setup_tray(app.handle())?;
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    app.run(|app_handle, event| {
+        // The sidecar was designed to be killed whenever the app exits, no
+        // matter which window/menu path triggered the exit.
+        if let tauri::RunEvent::Exit = event {
+            kill_on_exit(app_handle);
+        }
+    });
 }